@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 use std::io;
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
+use std::path::Path;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, EnvironmentIo, IoTrait};
+use vrc_get_vpm::unity_project::{BackupFormat, BackupProgress};
+use vrc_get_vpm::UnityProject;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +26,74 @@ pub struct GuiConfig {
     pub project_sorting: String,
 }
 
+impl GuiConfig {
+    /// The configured backup layout, parsed into the vpm [`BackupFormat`] that
+    /// drives `UnityProject::backup_to`.
+    pub fn backup_format(&self) -> BackupFormat {
+        BackupFormat::from_config(&self.backup_format)
+    }
+
+    /// Back `project` up to `dest` using the configured [`backup_format`].
+    ///
+    /// This is the wiring that turns the stored `backupFormat` string into
+    /// actual behaviour: backup commands call through here so a snapshot is
+    /// always written in the format the user picked. `progress` forwards
+    /// [`BackupProgress`] events to the GUI progress bar.
+    ///
+    /// [`backup_format`]: GuiConfig::backup_format
+    pub async fn backup_project(
+        &self,
+        project: &UnityProject,
+        dest: &Path,
+        progress: impl FnMut(BackupProgress),
+    ) -> io::Result<()> {
+        project.backup_to(dest, self.backup_format(), progress).await
+    }
+}
+
+/// A [`BackupProgress`] event serialised for the frontend progress bar.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupProgressEvent {
+    pub processed: u64,
+    pub total: u64,
+    pub current: String,
+}
+
+/// Back the Unity project at `project_path` up to `backup_path` using the
+/// layout stored in the GUI config.
+///
+/// This is the runtime entry point the frontend invokes, so the persisted
+/// `backupFormat` setting actually drives a backup instead of being an inert
+/// string: it loads the config, opens the project, and calls
+/// [`GuiConfig::backup_project`], forwarding every [`BackupProgress`] event to
+/// `channel` so the GUI can drive a progress bar.
+#[tauri::command]
+pub async fn project_create_backup(
+    config: tauri::State<'_, tokio::sync::Mutex<GuiConfigHolder>>,
+    io: tauri::State<'_, DefaultEnvironmentIo>,
+    project_path: String,
+    backup_path: String,
+    channel: tauri::ipc::Channel<BackupProgressEvent>,
+) -> Result<(), String> {
+    let mut holder = config.lock().await;
+    let config = holder.load(&io).await.map_err(|e| e.to_string())?;
+    let project = UnityProject::find_unity_project(Some(project_path.into()))
+        .await
+        .map_err(|e| e.to_string())?;
+    config
+        .backup_project(&project, backup_path.as_ref(), |progress: BackupProgress| {
+            let _ = channel.send(BackupProgressEvent {
+                processed: progress.processed,
+                total: progress.total,
+                current: progress.current.to_string_lossy().into_owned(),
+            });
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 fn language_default() -> String {
     "en".to_string()
 }
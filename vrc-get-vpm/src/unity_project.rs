@@ -1,4 +1,5 @@
 mod add_package;
+mod backup;
 mod package_resolution;
 mod remove_package;
 mod vpm_manifest;
@@ -11,7 +12,6 @@ use crate::{Environment, PackageInfo, VersionSelector};
 use futures::future::try_join_all;
 use futures::prelude::*;
 use indexmap::IndexMap;
-use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::{env, fmt, io};
@@ -24,6 +24,7 @@ use tokio::io::AsyncReadExt;
 use crate::traits::{HttpClient, PackageCollection};
 use crate::unity_project::add_package::add_package;
 pub use add_package::{AddPackageErr, AddPackageRequest};
+pub use backup::{BackupFormat, BackupProgress};
 
 #[derive(Debug)]
 pub struct UnityProject {
@@ -200,24 +201,64 @@ impl UnityProject {
 pub enum ResolvePackageErr {
     Io(io::Error),
     ConflictWithDependencies {
-        /// conflicting package name
-        conflict: String,
-        /// the name of locked package
-        dependency_name: String,
+        /// the chain of package names from the project root down to the
+        /// requirement that could not be satisfied
+        package_path: Vec<String>,
+        /// every package that requires the conflicting dependency, mapped to
+        /// the version range it demanded
+        conflicting_requirements: HashMap<String, VersionRange>,
     },
     DependencyNotFound {
         dependency_name: String,
     },
 }
 
+impl ResolvePackageErr {
+    /// The chain of package names from the project root down to the
+    /// requirement that failed, or an empty slice for non-conflict errors.
+    pub fn package_path(&self) -> &[String] {
+        match self {
+            ResolvePackageErr::ConflictWithDependencies { package_path, .. } => package_path,
+            _ => &[],
+        }
+    }
+
+    /// The competing requirements (requirer -> demanded range) for a conflict,
+    /// or `None` for non-conflict errors.
+    pub fn conflicting_requirements(&self) -> Option<&HashMap<String, VersionRange>> {
+        match self {
+            ResolvePackageErr::ConflictWithDependencies {
+                conflicting_requirements,
+                ..
+            } => Some(conflicting_requirements),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ResolvePackageErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ResolvePackageErr::Io(ioerr) => fmt::Display::fmt(ioerr, f),
             ResolvePackageErr::ConflictWithDependencies {
-                conflict,
-                dependency_name,
-            } => write!(f, "{conflict} conflicts with {dependency_name}"),
+                package_path,
+                conflicting_requirements,
+            } => {
+                if package_path.is_empty() {
+                    write!(f, "conflicting dependencies")?;
+                } else {
+                    write!(f, "{}", package_path.join(" -> "))?;
+                }
+                let mut competing = conflicting_requirements
+                    .iter()
+                    .map(|(requirer, range)| format!("{requirer} requires {range}"))
+                    .collect::<Vec<_>>();
+                competing.sort();
+                if !competing.is_empty() {
+                    write!(f, ": {}", competing.join(", "))?;
+                }
+                Ok(())
+            }
             ResolvePackageErr::DependencyNotFound { dependency_name } => write!(
                 f,
                 "Package {dependency_name} (maybe dependencies of the package) not found"
@@ -234,6 +275,11 @@ impl From<io::Error> for ResolvePackageErr {
     }
 }
 
+// The public `add_package` path only ever surfaces a missing dependency:
+// conflict diagnosis — the root→leaf chain and the competing-requirement map —
+// is produced exclusively by the PubGrub `resolve` path, which is the single
+// place with the full requirement graph to explain a conflict. `AddPackageErr`
+// has no conflict variant to map here.
 impl From<AddPackageErr> for ResolvePackageErr {
     fn from(value: AddPackageErr) -> Self {
         match value {
@@ -289,47 +335,54 @@ impl UnityProject {
             .map(|x| x.name())
             .collect();
 
-        // then, process dependencies of unlocked packages.
-        let unlocked_dependencies = self
+        // then, process dependencies of unlocked packages with the PubGrub
+        // solver so resolution is complete and conflicts are fully explained.
+        // Keep *every* range each unlocked package demands, not one per name: if
+        // two packages depend on the same package with different ranges the
+        // solver needs both as separate root incompatibilities, otherwise a
+        // dropped range could be silently violated.
+        let requirements: Vec<(String, VersionRange)> = self
             .unlocked_packages
             .iter()
             .filter_map(|(_, pkg)| pkg.as_ref())
             .flat_map(|pkg| pkg.vpm_dependencies())
             .filter(|(k, _)| self.manifest.get_locked(k.as_str()).is_none())
             .filter(|(k, _)| !unlocked_names.contains(k.as_str()))
-            .into_group_map()
-            .into_iter()
-            .map(|(pkg_name, ranges)| {
-                env.find_package_by_name(
-                    pkg_name,
-                    VersionSelector::ranges_for(self.unity_version, &ranges),
-                )
-                .ok_or_else(|| ResolvePackageErr::DependencyNotFound {
-                    dependency_name: pkg_name.clone(),
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let allow_prerelease = unlocked_dependencies
-            .iter()
-            .any(|x| !x.version().pre.is_empty());
+            .map(|(name, range)| (name.clone(), range.clone()))
+            .collect();
 
-        let req = self
-            .add_package_request(env, unlocked_dependencies, false, allow_prerelease)
-            .await?;
+        let locked: HashMap<String, Version> = self
+            .manifest
+            .all_locked()
+            .map(|dep| (dep.name().to_owned(), dep.version().clone()))
+            .collect();
 
-        if !req.conflicts.is_empty() {
-            let (conflict, mut deps) = req.conflicts.into_iter().next().unwrap();
-            return Err(ResolvePackageErr::ConflictWithDependencies {
-                conflict,
-                dependency_name: deps.swap_remove(0),
-            });
+        let installed_from_unlocked_dependencies =
+            package_resolution::resolve(env, &requirements, self.unity_version, &locked)
+                .map_err(|err| match err {
+                    package_resolution::ResolutionError::NotFound(dependency_name) => {
+                        ResolvePackageErr::DependencyNotFound { dependency_name }
+                    }
+                    package_resolution::ResolutionError::Conflict(no_solution) => {
+                        ResolvePackageErr::ConflictWithDependencies {
+                            package_path: no_solution.package_path(),
+                            conflicting_requirements: no_solution.conflicting_requirements(),
+                        }
+                    }
+                })?;
+
+        for package in &installed_from_unlocked_dependencies {
+            add_package(env, *package, packages_folder).await?;
+            // Lock the newly-resolved dependency into vpm-manifest.json so a
+            // later `mark_and_sweep` treats it as wanted; writing only to
+            // Packages/ would leave it unreferenced and eligible for deletion.
+            self.manifest.add_locked(
+                package.name(),
+                package.version().clone(),
+                package.vpm_dependencies().clone(),
+            );
         }
 
-        let installed_from_unlocked_dependencies = req.locked.clone();
-
-        self.do_add_package_request(env, req).await?;
-
         Ok(ResolveResult {
             installed_from_locked,
             installed_from_unlocked_dependencies,
@@ -357,6 +410,164 @@ impl UnityProject {
     }
 }
 
+/// Severity of a single [`ProjectDiagnostics`] entry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DiagnosticSeverity {
+    /// Something that will prevent the project from resolving correctly.
+    Error,
+    /// Something suspicious the user should look at but that still works.
+    Warning,
+}
+
+/// A single health-report finding about the project.
+#[derive(Debug)]
+pub struct Diagnostic {
+    severity: DiagnosticSeverity,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> DiagnosticSeverity {
+        self.severity
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A structured health report for a [`UnityProject`], suitable for display in
+/// the GUI or CLI instead of being swallowed by the loaders' `NotFound`
+/// handling.
+#[derive(Debug)]
+pub struct ProjectDiagnostics {
+    unity_version: Option<UnityVersion>,
+    entries: Vec<Diagnostic>,
+}
+
+impl ProjectDiagnostics {
+    /// The Unity version parsed from `ProjectVersion.txt`, if any.
+    pub fn unity_version(&self) -> Option<UnityVersion> {
+        self.unity_version
+    }
+
+    /// All findings, most severe first is not guaranteed; entries follow the
+    /// order they were discovered.
+    pub fn entries(&self) -> &[Diagnostic] {
+        &self.entries
+    }
+
+    /// Whether any entry is an [`DiagnosticSeverity::Error`].
+    pub fn has_errors(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.severity == DiagnosticSeverity::Error)
+    }
+
+    fn push(&mut self, severity: DiagnosticSeverity, message: impl Into<String>) {
+        self.entries.push(Diagnostic {
+            severity,
+            message: message.into(),
+        });
+    }
+}
+
+// diagnostics
+impl UnityProject {
+    /// Collect a structured health report cross-checking the manifest against
+    /// the on-disk project and the installed repositories.
+    pub async fn diagnostics(
+        &self,
+        env: &Environment<impl HttpClient>,
+    ) -> io::Result<ProjectDiagnostics> {
+        let mut report = ProjectDiagnostics {
+            unity_version: self.unity_version,
+            entries: Vec::new(),
+        };
+
+        if self.unity_version.is_none() {
+            report.push(
+                DiagnosticSeverity::Error,
+                "ProjectVersion.txt is missing or its Unity version could not be parsed",
+            );
+        }
+
+        let packages_folder = self.project_dir.join("Packages");
+
+        for locked in self.manifest.all_locked() {
+            let folder = packages_folder.join(locked.name());
+            match tokio::fs::metadata(&folder).await {
+                Ok(meta) if meta.is_dir() => {}
+                _ => {
+                    report.push(
+                        DiagnosticSeverity::Error,
+                        format!("locked package {} has no folder", locked.name()),
+                    );
+                }
+            }
+
+            if let Some(installed) = self.installed_packages.get(locked.name()) {
+                if installed.version() != locked.version() {
+                    report.push(
+                        DiagnosticSeverity::Warning,
+                        format!(
+                            "installed {} but locked {} for package {}",
+                            installed.version(),
+                            locked.version(),
+                            locked.name()
+                        ),
+                    );
+                }
+            }
+
+            // Flag a locked entry whose *name* is unknown to every repository.
+            // Querying the specific locked version would also fire when the
+            // package exists but only at other versions, which is a different
+            // (and less alarming) situation than a missing name.
+            let any_version = VersionRange::all();
+            if env
+                .find_package_by_name(
+                    locked.name(),
+                    VersionSelector::ranges_for(None, &[&any_version]),
+                )
+                .is_none()
+            {
+                report.push(
+                    DiagnosticSeverity::Warning,
+                    format!(
+                        "locked package {} is not present in any repository",
+                        locked.name()
+                    ),
+                );
+            }
+        }
+
+        for (_, pkg) in self.unlocked_packages.iter() {
+            let Some(pkg) = pkg else { continue };
+            for (dep_name, _) in pkg.vpm_dependencies() {
+                let resolved = self.manifest.get_locked(dep_name).is_some()
+                    || self
+                        .unlocked_packages
+                        .iter()
+                        .filter_map(|(_, p)| p.as_ref())
+                        .any(|p| p.name() == dep_name);
+                if !resolved {
+                    report.push(
+                        DiagnosticSeverity::Warning,
+                        format!(
+                            "unlocked package {} depends on unresolved {}",
+                            pkg.name(),
+                            dep_name
+                        ),
+                    );
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
 // accessors
 impl UnityProject {
     pub fn locked_packages(&self) -> impl Iterator<Item = LockedDependencyInfo> {
@@ -396,6 +607,110 @@ impl UnityProject {
     }
 }
 
+/// The materialized dependency graph of every package known to the project
+/// (locked and unlocked), with both forward and reverse edges.
+///
+/// This is the same shape cargo's resolver keeps as a package path and backs
+/// a `cargo tree`-style view. `mark_and_sweep` does not use it and still
+/// returns only the flat set of removed package names.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// package name -> names it depends on
+    edges: HashMap<String, Vec<String>>,
+    /// package name -> names that depend on it
+    reverse: HashMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// The direct dependencies of `name` (the packages it requires).
+    pub fn dependencies_of(&self, name: &str) -> &[String] {
+        self.edges.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The direct dependents of `name` (the packages that require it).
+    pub fn dependents_of(&self, name: &str) -> &[String] {
+        self.reverse.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// All package names in the graph.
+    pub fn packages(&self) -> impl Iterator<Item = &str> {
+        self.edges.keys().map(String::as_str)
+    }
+
+    /// Whether the graph contains at least one cycle.
+    pub fn has_cycle(&self) -> bool {
+        self.topological_order().is_none()
+    }
+
+    /// A topological ordering (dependencies before dependents), or `None` if
+    /// the graph contains a cycle.
+    pub fn topological_order(&self) -> Option<Vec<String>> {
+        // Order a package after every package it depends on: its in-degree is
+        // the number of its own unresolved dependencies, and emitting it
+        // releases the packages that depend on it (its reverse edges).
+        let mut remaining: HashMap<&str, usize> = self
+            .edges
+            .iter()
+            .map(|(name, deps)| {
+                // only dependencies that are themselves graph nodes can order us
+                let degree = deps.iter().filter(|d| self.edges.contains_key(*d)).count();
+                (name.as_str(), degree)
+            })
+            .collect();
+
+        let mut queue: Vec<&str> = remaining
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&name, _)| name)
+            .collect();
+        queue.sort_unstable();
+
+        let mut order = Vec::with_capacity(remaining.len());
+        while let Some(name) = queue.pop() {
+            order.push(name.to_owned());
+            for dependent in self.dependents_of(name) {
+                if let Some(degree) = remaining.get_mut(dependent.as_str()) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        queue.push(dependent.as_str());
+                        queue.sort_unstable();
+                    }
+                }
+            }
+        }
+
+        (order.len() == remaining.len()).then_some(order)
+    }
+}
+
+// dependency graph
+impl UnityProject {
+    /// Materialize the full dependency graph for the project, including both
+    /// the forward edges to each package's `vpm_dependencies` and the reverse
+    /// ("who depends on me") edges.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+
+        for package in self.all_packages() {
+            let deps: Vec<String> = package
+                .dependencies()
+                .keys()
+                .map(|name| name.to_owned())
+                .collect();
+            for dep in &deps {
+                graph
+                    .reverse
+                    .entry(dep.clone())
+                    .or_default()
+                    .push(package.name().to_owned());
+            }
+            graph.edges.insert(package.name().to_owned(), deps);
+        }
+
+        graph
+    }
+}
+
 pub struct LockedDependencyInfo<'a> {
     name: &'a str,
     version: &'a Version,
@@ -426,4 +741,100 @@ impl<'a> LockedDependencyInfo<'a> {
     pub fn dependencies(&self) -> &'a IndexMap<String, VersionRange> {
         self.dependencies
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_report_tracks_severity() {
+        let mut report = ProjectDiagnostics {
+            unity_version: None,
+            entries: Vec::new(),
+        };
+        assert!(!report.has_errors());
+
+        report.push(DiagnosticSeverity::Warning, "installed 1.2.0 but locked 1.1.0");
+        assert!(!report.has_errors());
+        assert_eq!(report.entries().len(), 1);
+        assert_eq!(report.entries()[0].severity(), DiagnosticSeverity::Warning);
+
+        report.push(DiagnosticSeverity::Error, "locked package Foo has no folder");
+        assert!(report.has_errors());
+        assert_eq!(report.entries().len(), 2);
+    }
+
+    #[test]
+    fn conflict_display_renders_chain_and_competing_ranges() {
+        let mut requirements = HashMap::new();
+        requirements.insert("com.example.foo".to_owned(), VersionRange::all());
+        let err = ResolvePackageErr::ConflictWithDependencies {
+            package_path: vec![
+                "com.example.root".to_owned(),
+                "com.example.mid".to_owned(),
+                "com.example.foo".to_owned(),
+            ],
+            conflicting_requirements: requirements,
+        };
+
+        let rendered = err.to_string();
+        assert!(rendered.contains("com.example.root -> com.example.mid -> com.example.foo"));
+        assert!(rendered.contains("com.example.foo requires"));
+        // the chain must never surface negated dependency terms
+        assert!(!rendered.contains("not "));
+    }
+
+    fn edge(graph: &mut DependencyGraph, from: &str, to: &str) {
+        graph
+            .edges
+            .entry(from.to_owned())
+            .or_default()
+            .push(to.to_owned());
+        graph.edges.entry(to.to_owned()).or_default();
+        graph
+            .reverse
+            .entry(to.to_owned())
+            .or_default()
+            .push(from.to_owned());
+    }
+
+    #[test]
+    fn topological_order_lists_dependencies_before_dependents() {
+        // a -> b -> c
+        let mut graph = DependencyGraph::default();
+        edge(&mut graph, "a", "b");
+        edge(&mut graph, "b", "c");
+
+        let order = graph.topological_order().expect("acyclic");
+        let position = |name: &str| order.iter().position(|n| n == name).unwrap();
+        assert!(position("c") < position("b"));
+        assert!(position("b") < position("a"));
+        assert!(!graph.has_cycle());
+    }
+
+    #[test]
+    fn topological_order_detects_cycles() {
+        let mut graph = DependencyGraph::default();
+        edge(&mut graph, "a", "b");
+        edge(&mut graph, "b", "a");
+
+        assert!(graph.topological_order().is_none());
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn conflict_accessors_expose_path_and_requirements() {
+        let err = ResolvePackageErr::ConflictWithDependencies {
+            package_path: vec!["a".to_owned(), "b".to_owned()],
+            conflicting_requirements: HashMap::new(),
+        };
+        assert_eq!(err.package_path(), ["a".to_owned(), "b".to_owned()]);
+        assert!(err.conflicting_requirements().is_some());
+
+        let other = ResolvePackageErr::DependencyNotFound {
+            dependency_name: "x".to_owned(),
+        };
+        assert!(other.package_path().is_empty());
+        assert!(other.conflicting_requirements().is_none());
+    }
+}
@@ -0,0 +1,285 @@
+// Project backup / restore subsystem.
+//
+// `GuiConfig.backup_format` used to be a string that nothing acted on. This
+// module turns it into working behaviour: given a [`UnityProject`] it produces
+// an archive in the configured format and can reconstruct the project from one
+// again, so users have a recoverable snapshot before a risky `resolve`.
+
+use crate::unity_project::UnityProject;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directories that are regenerated by Unity and are never worth backing up.
+const SKIP_LIST: &[&str] = &["Library", "Temp", "Logs", "obj"];
+
+/// The archive layout produced by [`UnityProject::backup_to`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BackupFormat {
+    /// An uncompressed copy of the project directory tree.
+    Default,
+    /// A single `.zip` archive.
+    Zip,
+    /// A gzip-compressed tarball (`.tar.gz`).
+    TarGz,
+}
+
+impl BackupFormat {
+    /// Parse the `backup_format` config string. Unknown values fall back to
+    /// [`BackupFormat::Default`] to match the config's own default.
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "zip" => BackupFormat::Zip,
+            "tar.gz" | "tgz" | "tar" => BackupFormat::TarGz,
+            _ => BackupFormat::Default,
+        }
+    }
+}
+
+/// Progress event emitted while an archive is written or read, so the GUI can
+/// drive a progress bar.
+#[derive(Clone, Debug)]
+pub struct BackupProgress {
+    /// Number of project files processed so far.
+    pub processed: u64,
+    /// Total number of project files to process.
+    pub total: u64,
+    /// The file currently being processed, relative to the project root.
+    pub current: PathBuf,
+}
+
+impl UnityProject {
+    /// Write an archive of this project to `path` using `format`, skipping the
+    /// Unity-regenerated directories in [`SKIP_LIST`].
+    ///
+    /// `progress` is invoked once per archived file.
+    pub async fn backup_to(
+        &self,
+        path: &Path,
+        format: BackupFormat,
+        mut progress: impl FnMut(BackupProgress),
+    ) -> io::Result<()> {
+        let files = collect_files(self.project_dir()).await?;
+        let total = files.len() as u64;
+
+        match format {
+            BackupFormat::Default => {
+                for (processed, relative) in files.iter().enumerate() {
+                    let destination = path.join(relative);
+                    if let Some(parent) = destination.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    tokio::fs::copy(self.project_dir().join(relative), &destination).await?;
+                    progress(BackupProgress {
+                        processed: processed as u64 + 1,
+                        total,
+                        current: relative.clone(),
+                    });
+                }
+            }
+            BackupFormat::Zip | BackupFormat::TarGz => {
+                write_archive(self.project_dir(), path, format, &files, &mut progress).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reconstruct a project from `archive` into this project's directory,
+    /// restoring `Packages/vpm-manifest.json` and the package folders.
+    ///
+    /// The Unity version recorded in the restored `ProjectVersion.txt` is
+    /// checked against this project's [`unity_version`](UnityProject::unity_version);
+    /// a mismatch is reported as an error so the caller can warn the user.
+    pub async fn restore_from(&self, archive: &Path) -> io::Result<()> {
+        extract_archive(archive, self.project_dir()).await?;
+
+        let restored = Self::try_read_unity_version(self.project_dir()).await;
+        match (self.unity_version(), restored) {
+            (Some(expected), Some(found)) if expected != found => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("restored Unity version {found} does not match project {expected}"),
+            )),
+            (_, None) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "restored project is missing a readable ProjectVersion.txt",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Recursively list every file under `root`, relative to `root`, skipping the
+/// directories in [`SKIP_LIST`].
+async fn collect_files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let mut reading = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = reading.next_entry().await? {
+            let path = entry.path();
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                let skip = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| SKIP_LIST.contains(&name));
+                if !skip {
+                    stack.push(path);
+                }
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Compress `files` under `root` into a single archive at `path`. Each file is
+/// read and compressed in turn and a [`BackupProgress`] event is emitted
+/// *after* it has been written, so the GUI bar reflects real progress.
+async fn write_archive(
+    root: &Path,
+    path: &Path,
+    format: BackupFormat,
+    files: &[PathBuf],
+    progress: &mut impl FnMut(BackupProgress),
+) -> io::Result<()> {
+    let total = files.len() as u64;
+    let root = root.to_path_buf();
+    let path = path.to_path_buf();
+    let files = files.to_vec();
+
+    // Compression is CPU-bound, so run it on the blocking pool like
+    // `extract_archive` and stream progress back over a channel rather than
+    // stalling the tokio runtime.
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<BackupProgress>();
+
+    let writer = tokio::task::spawn_blocking(move || -> io::Result<()> {
+        let output = std::fs::File::create(&path)?;
+        match format {
+            BackupFormat::Zip => {
+                let mut zip = zip::ZipWriter::new(output);
+                let options = zip::write::FileOptions::default();
+                for (processed, relative) in files.iter().enumerate() {
+                    zip.start_file(relative.to_string_lossy(), options)?;
+                    let bytes = std::fs::read(root.join(relative))?;
+                    std::io::Write::write_all(&mut zip, &bytes)?;
+                    let _ = tx.send(BackupProgress {
+                        processed: processed as u64 + 1,
+                        total,
+                        current: relative.clone(),
+                    });
+                }
+                zip.finish()?;
+            }
+            BackupFormat::TarGz => {
+                let encoder =
+                    flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                let mut tar = tar::Builder::new(encoder);
+                for (processed, relative) in files.iter().enumerate() {
+                    let bytes = std::fs::read(root.join(relative))?;
+                    let mut header = tar::Header::new_gnu();
+                    header.set_size(bytes.len() as u64);
+                    header.set_mode(0o644);
+                    header.set_cksum();
+                    tar.append_data(&mut header, relative, bytes.as_slice())?;
+                    let _ = tx.send(BackupProgress {
+                        processed: processed as u64 + 1,
+                        total,
+                        current: relative.clone(),
+                    });
+                }
+                tar.into_inner()?.finish()?;
+            }
+            BackupFormat::Default => unreachable!("default format is copied, not archived"),
+        }
+        Ok(())
+    });
+
+    // Drain progress events until the blocking task drops `tx`, then surface
+    // the archive's own result (or a join failure).
+    while let Some(event) = rx.recv().await {
+        progress(event);
+    }
+    writer
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
+
+/// Extract a `default` directory copy, `.zip`, or `.tar.gz` archive at
+/// `archive` into `destination`.
+async fn extract_archive(archive: &Path, destination: &Path) -> io::Result<()> {
+    let archive = archive.to_path_buf();
+    let destination = destination.to_path_buf();
+
+    if archive.is_dir() {
+        let files = collect_files(&archive).await?;
+        for relative in files {
+            let target = destination.join(&relative);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::copy(archive.join(&relative), target).await?;
+        }
+        return Ok(());
+    }
+
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        let input = std::fs::File::open(&archive)?;
+        match BackupFormat::from_config(
+            archive
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or_default(),
+        ) {
+            BackupFormat::Zip => {
+                let mut zip = zip::ZipArchive::new(input)?;
+                zip.extract(&destination)?;
+            }
+            _ => {
+                let decoder = flate2::read::GzDecoder::new(input);
+                tar::Archive::new(decoder).unpack(&destination)?;
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_format_parses_config_strings() {
+        assert_eq!(BackupFormat::from_config("default"), BackupFormat::Default);
+        assert_eq!(BackupFormat::from_config("zip"), BackupFormat::Zip);
+        assert_eq!(BackupFormat::from_config("tar.gz"), BackupFormat::TarGz);
+        assert_eq!(BackupFormat::from_config("tgz"), BackupFormat::TarGz);
+        // unknown values fall back to the default, matching the config default
+        assert_eq!(BackupFormat::from_config("nonsense"), BackupFormat::Default);
+    }
+
+    #[tokio::test]
+    async fn collect_files_skips_regenerated_directories() {
+        let root = std::env::temp_dir().join("vrc-get-backup-test");
+        let _ = tokio::fs::remove_dir_all(&root).await;
+        tokio::fs::create_dir_all(root.join("Assets")).await.unwrap();
+        tokio::fs::create_dir_all(root.join("Library")).await.unwrap();
+        tokio::fs::write(root.join("Assets").join("a.cs"), b"//")
+            .await
+            .unwrap();
+        tokio::fs::write(root.join("Library").join("cache"), b"x")
+            .await
+            .unwrap();
+
+        let files = collect_files(&root).await.unwrap();
+        assert!(files.contains(&PathBuf::from("Assets").join("a.cs")));
+        assert!(files.iter().all(|f| !f.starts_with("Library")));
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
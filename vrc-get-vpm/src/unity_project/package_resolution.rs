@@ -0,0 +1,671 @@
+// A conflict-driven version-constraint solver based on the PubGrub algorithm.
+//
+// The ad-hoc resolver used to walk the dependency graph once, picking the first
+// version that satisfied every requirement seen so far and giving up on the
+// first conflict. That made optimal resolution impossible and the error
+// messages useless. This module models the problem the way PubGrub does:
+//
+// * a [`Term`] is a package name plus a [`Constraint`], either positive
+//   ("the solution must contain a version in this range") or negative ("the
+//   solution must not contain a version in this range"),
+// * an [`Incompatibility`] is a set of terms that can never all hold at once,
+// * the [`PartialSolution`] is the ordered list of assignments — *decisions*
+//   (a concrete chosen version) and *derivations* (a term implied by unit
+//   propagation, tagged with the incompatibility that caused it).
+//
+// Unit propagation and satisfaction are evaluated against the *whole* partial
+// solution — both decisions and derivations — so a term that has already been
+// derived is never derived again. See the reference description at
+// <https://github.com/dart-lang/pub/blob/master/doc/solver.md>.
+
+use crate::traits::PackageCollection;
+use crate::version::{Version, VersionRange};
+use crate::{PackageInfo, UnityVersion, VersionSelector};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The set of versions a [`Term`] constrains. Requirements carry a full
+/// [`VersionRange`]; a package's own decision is modelled as an exact version
+/// so a dependency constraint pins the *decided* version rather than "anything
+/// at least this version".
+#[derive(Clone, Debug, PartialEq)]
+enum Constraint {
+    Range(VersionRange),
+    Exact(Version),
+}
+
+impl Constraint {
+    fn matches(&self, version: &Version) -> bool {
+        match self {
+            Constraint::Range(range) => range.matches(version),
+            Constraint::Exact(exact) => version == exact,
+        }
+    }
+}
+
+/// A single requirement: a package must (positive) or must not (negative) be
+/// resolved to a version allowed by `constraint`.
+#[derive(Clone, Debug, PartialEq)]
+struct Term {
+    package: String,
+    constraint: Constraint,
+    positive: bool,
+}
+
+impl Term {
+    fn positive(package: impl Into<String>, constraint: Constraint) -> Self {
+        Self {
+            package: package.into(),
+            constraint,
+            positive: true,
+        }
+    }
+
+    fn negative(package: impl Into<String>, constraint: Constraint) -> Self {
+        Self {
+            package: package.into(),
+            constraint,
+            positive: false,
+        }
+    }
+
+    fn negate(&self) -> Self {
+        Self {
+            package: self.package.clone(),
+            constraint: self.constraint.clone(),
+            positive: !self.positive,
+        }
+    }
+
+    /// Whether a concrete `version` of this package honours the term.
+    fn holds_for(&self, version: &Version) -> bool {
+        self.constraint.matches(version) == self.positive
+    }
+}
+
+/// A set of terms that cannot all be true simultaneously. The cause that
+/// derived a term is recorded on its [`Assignment`] rather than here, which is
+/// what lets conflict resolution walk the derivation tree.
+#[derive(Clone, Debug)]
+struct Incompatibility {
+    terms: Vec<Term>,
+}
+
+impl Incompatibility {
+    fn new(terms: Vec<Term>) -> Self {
+        Self { terms }
+    }
+}
+
+/// An entry in the partial solution.
+#[derive(Debug)]
+struct Assignment {
+    term: Term,
+    level: usize,
+    /// `Some(index)` for a derivation (the incompatibility that caused it),
+    /// `None` for a decision.
+    cause: Option<usize>,
+}
+
+impl Assignment {
+    fn is_decision(&self) -> bool {
+        self.cause.is_none()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Satisfaction {
+    Satisfied,
+    Contradicted,
+    Inconclusive,
+}
+
+enum Relation {
+    /// Every term is satisfied — a genuine conflict.
+    Satisfied,
+    /// At least one term is contradicted — the incompatibility is a no-op.
+    Contradicted,
+    /// Exactly one term is undetermined (its index) and the rest are satisfied.
+    AlmostSatisfied(usize),
+    Inconclusive,
+}
+
+/// The ordered list of assignments made so far plus the per-package decisions.
+#[derive(Default, Debug)]
+struct PartialSolution {
+    assignments: Vec<Assignment>,
+    decisions: HashMap<String, Version>,
+    level: usize,
+}
+
+impl PartialSolution {
+    fn decide(&mut self, package: String, version: Version) {
+        self.level += 1;
+        self.decisions.insert(package.clone(), version.clone());
+        self.assignments.push(Assignment {
+            term: Term::positive(package, Constraint::Exact(version)),
+            level: self.level,
+            cause: None,
+        });
+    }
+
+    fn derive(&mut self, term: Term, cause: usize) {
+        self.assignments.push(Assignment {
+            term,
+            level: self.level,
+            cause: Some(cause),
+        });
+    }
+
+    fn decided(&self, package: &str) -> Option<&Version> {
+        self.decisions.get(package)
+    }
+
+    /// How the partial solution relates to a single term, consulting both
+    /// decisions and derivations.
+    fn term_status(&self, term: &Term) -> Satisfaction {
+        if let Some(version) = self.decided(&term.package) {
+            return if term.holds_for(version) {
+                Satisfaction::Satisfied
+            } else {
+                Satisfaction::Contradicted
+            };
+        }
+        for assignment in &self.assignments {
+            let derived = &assignment.term;
+            if derived.package == term.package && derived.constraint == term.constraint {
+                return if derived.positive == term.positive {
+                    Satisfaction::Satisfied
+                } else {
+                    Satisfaction::Contradicted
+                };
+            }
+        }
+        Satisfaction::Inconclusive
+    }
+
+    fn relation(&self, incompat: &Incompatibility) -> Relation {
+        let mut unsatisfied = None;
+        for (index, term) in incompat.terms.iter().enumerate() {
+            match self.term_status(term) {
+                Satisfaction::Contradicted => return Relation::Contradicted,
+                Satisfaction::Inconclusive => {
+                    if unsatisfied.is_some() {
+                        return Relation::Inconclusive;
+                    }
+                    unsatisfied = Some(index);
+                }
+                Satisfaction::Satisfied => {}
+            }
+        }
+        match unsatisfied {
+            None => Relation::Satisfied,
+            Some(index) => Relation::AlmostSatisfied(index),
+        }
+    }
+
+    /// Index of the assignment that makes `term` satisfied, if any.
+    fn satisfier(&self, term: &Term) -> Option<usize> {
+        self.assignments.iter().position(|assignment| {
+            let a = &assignment.term;
+            if a.package != term.package {
+                return false;
+            }
+            if assignment.is_decision() {
+                term.holds_for(self.decisions.get(&term.package).expect("decided"))
+            } else {
+                a.positive == term.positive && a.constraint == term.constraint
+            }
+        })
+    }
+
+    fn already_derived(&self, term: &Term) -> bool {
+        self.assignments.iter().any(|a| &a.term == term)
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        self.assignments.retain(|a| a.level <= level);
+        self.decisions.clear();
+        for assignment in &self.assignments {
+            if assignment.is_decision() {
+                if let Constraint::Exact(version) = &assignment.term.constraint {
+                    self.decisions
+                        .insert(assignment.term.package.clone(), version.clone());
+                }
+            }
+        }
+        self.level = level;
+    }
+}
+
+/// A dependency edge recorded as the graph is explored, used to render the
+/// root→leaf chain and the competing requirements when resolution fails.
+#[derive(Clone, Debug)]
+struct Edge {
+    /// `None` for a direct project dependency.
+    requirer: Option<String>,
+    dependency: String,
+    range: VersionRange,
+}
+
+/// Failure carrying the diagnosable conflict: the chain of package names from
+/// the project root down to the failing requirement and the map of every
+/// requirer to the range it demanded.
+#[derive(Debug)]
+pub(crate) struct NoSolution {
+    package_path: Vec<String>,
+    conflicting_requirements: HashMap<String, VersionRange>,
+}
+
+impl NoSolution {
+    pub(crate) fn package_path(&self) -> Vec<String> {
+        self.package_path.clone()
+    }
+
+    pub(crate) fn conflicting_requirements(&self) -> HashMap<String, VersionRange> {
+        self.conflicting_requirements.clone()
+    }
+}
+
+impl fmt::Display for NoSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version solving failed for {}", self.package_path.join(" -> "))
+    }
+}
+
+/// Either a package that does not exist in any repository, or an unsatisfiable
+/// set of requirements.
+#[derive(Debug)]
+pub(crate) enum ResolutionError {
+    NotFound(String),
+    Conflict(NoSolution),
+}
+
+/// Resolve `requirements` (the project's direct VPM dependencies) into a
+/// complete, optimal set of packages using the PubGrub algorithm.
+///
+/// `requirements` is a flat list of `(name, range)` pairs rather than a map
+/// keyed by name: two unlocked packages can demand the same dependency with
+/// different ranges, and every range has to seed its own root incompatibility
+/// or the dropped constraint could be silently violated.
+///
+/// `locked` lists versions already pinned in `vpm-manifest.json`; they are
+/// preferred when they satisfy their term so that resolution stays stable.
+/// The regular pick queries `find_package_by_name` with a selector that carries
+/// `unity_version`, so it skips versions whose Unity compatibility range
+/// excludes it. A preferred locked version, and the fallback pick used when a
+/// learned clause excludes the selector's choice, are checked against the
+/// accumulated ranges and exclusions only, not against `unity_version`.
+pub(crate) fn resolve<'env>(
+    env: &'env impl PackageCollection,
+    requirements: &[(String, VersionRange)],
+    unity_version: Option<UnityVersion>,
+    locked: &HashMap<String, Version>,
+) -> Result<Vec<PackageInfo<'env>>, ResolutionError> {
+    let mut incompatibilities: Vec<Incompatibility> = Vec::new();
+    let mut edges: Vec<Edge> = Vec::new();
+
+    // Seed the solver with the root incompatibility `{ project requires … }`,
+    // expressed as the negation of each required range.
+    for (name, range) in requirements {
+        incompatibilities.push(Incompatibility::new(vec![Term::negative(
+            name.clone(),
+            Constraint::Range(range.clone()),
+        )]));
+        edges.push(Edge {
+            requirer: None,
+            dependency: name.clone(),
+            range: range.clone(),
+        });
+    }
+
+    let mut solution = PartialSolution::default();
+    let mut candidates: HashMap<String, PackageInfo<'env>> = HashMap::new();
+
+    loop {
+        // (1) unit propagation — may run conflict resolution and backjump.
+        propagate(&mut solution, &mut incompatibilities, &edges)?;
+
+        // (2) decision making: pick a package with a positive derived term but
+        // no decision yet.
+        let Some(package) = next_undecided(&solution) else {
+            break;
+        };
+
+        let ranges: Vec<&VersionRange> = solution
+            .assignments
+            .iter()
+            .filter(|a| a.term.package == package && a.term.positive)
+            .filter_map(|a| match &a.term.constraint {
+                Constraint::Range(range) => Some(range),
+                Constraint::Exact(_) => None,
+            })
+            .collect();
+
+        // Versions ruled out by a negative derivation (e.g. a learned clause
+        // `neg(X, Exact(V))`). Without this the selector would happily re-pick
+        // the very version a conflict just excluded, re-satisfy the learned
+        // incompatibility, and loop forever.
+        let excluded: Vec<&Constraint> = solution
+            .assignments
+            .iter()
+            .filter(|a| a.term.package == package && !a.term.positive)
+            .map(|a| &a.term.constraint)
+            .collect();
+
+        let selector = VersionSelector::ranges_for(unity_version, &ranges);
+        let Some(package_info) = pick_version(
+            env,
+            &package,
+            selector,
+            &ranges,
+            &excluded,
+            locked.get(&package),
+        ) else {
+            // No version satisfies the accumulated requirements. A single
+            // requirer means the dependency simply cannot be provided; several
+            // competing requirers mean a genuine conflict to diagnose.
+            let requirers = edges.iter().filter(|e| e.dependency == package).count();
+            if requirers <= 1 {
+                return Err(ResolutionError::NotFound(package));
+            }
+            return Err(ResolutionError::Conflict(build_no_solution(&edges, &package)));
+        };
+
+        let version = package_info.version().clone();
+        for (dep_name, dep_range) in package_info.vpm_dependencies() {
+            incompatibilities.push(Incompatibility::new(vec![
+                Term::positive(package.clone(), Constraint::Exact(version.clone())),
+                Term::negative(dep_name.clone(), Constraint::Range(dep_range.clone())),
+            ]));
+            edges.push(Edge {
+                requirer: Some(package.clone()),
+                dependency: dep_name.clone(),
+                range: dep_range.clone(),
+            });
+        }
+
+        candidates.insert(package.clone(), package_info);
+        solution.decide(package, version);
+    }
+
+    // Thread the result off the final partial solution so versions that were
+    // decided and later backtracked never leak into the installed set.
+    Ok(solution
+        .decisions
+        .iter()
+        .filter_map(|(name, version)| {
+            candidates
+                .get(name)
+                .filter(|info| info.version() == version)
+                .copied()
+        })
+        .collect())
+}
+
+fn propagate(
+    solution: &mut PartialSolution,
+    incompatibilities: &mut Vec<Incompatibility>,
+    edges: &[Edge],
+) -> Result<(), ResolutionError> {
+    loop {
+        let mut changed = false;
+        for index in 0..incompatibilities.len() {
+            match solution.relation(&incompatibilities[index]) {
+                Relation::Satisfied => {
+                    resolve_conflict(solution, incompatibilities, index, edges)?;
+                    changed = true;
+                    break;
+                }
+                Relation::AlmostSatisfied(term_index) => {
+                    let term = incompatibilities[index].terms[term_index].negate();
+                    if !solution.already_derived(&term) {
+                        solution.derive(term, index);
+                        changed = true;
+                    }
+                }
+                Relation::Contradicted | Relation::Inconclusive => {}
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+/// Walk back through the assignments, resolving the satisfied incompatibility
+/// against the prior cause of its most recent satisfier, until it becomes unit
+/// at an earlier decision level; then backjump to that level and learn it.
+fn resolve_conflict(
+    solution: &mut PartialSolution,
+    incompatibilities: &mut Vec<Incompatibility>,
+    index: usize,
+    edges: &[Edge],
+) -> Result<(), ResolutionError> {
+    let mut incompat = incompatibilities[index].clone();
+
+    loop {
+        if incompat.terms.is_empty() {
+            return Err(ResolutionError::Conflict(no_solution_from(&incompat, edges)));
+        }
+
+        let satisfiers: Vec<usize> = incompat
+            .terms
+            .iter()
+            .map(|term| solution.satisfier(term).expect("satisfied incompatibility"))
+            .collect();
+
+        let most_recent = satisfiers
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &idx)| idx)
+            .map(|(pos, &idx)| (pos, idx))
+            .expect("non-empty");
+
+        let satisfier = &solution.assignments[most_recent.1];
+        let satisfier_level = satisfier.level;
+        // The backjump level is the highest decision level among the *other*
+        // satisfiers; a single-term learned clause has none, so it backjumps to
+        // the root (level 0) where the `previous_level == 0` guard can fire.
+        let previous_level = satisfiers
+            .iter()
+            .enumerate()
+            .filter(|(pos, _)| *pos != most_recent.0)
+            .map(|(_, &idx)| solution.assignments[idx].level)
+            .max()
+            .unwrap_or(0);
+
+        if satisfier.is_decision() || previous_level < satisfier_level {
+            if previous_level == 0 {
+                return Err(ResolutionError::Conflict(no_solution_from(&incompat, edges)));
+            }
+            solution.backtrack(previous_level);
+            incompatibilities.push(incompat);
+            return Ok(());
+        }
+
+        // Resolve with the prior cause of the satisfier derivation.
+        let cause = satisfier.cause.expect("derivation has a cause");
+        let package = satisfier.term.package.clone();
+        let prior = incompatibilities[cause].clone();
+        incompat = resolve_incompatibilities(&incompat, &prior, &package);
+    }
+}
+
+/// The union of two incompatibilities' terms, dropping every term about the
+/// satisfier's package (the term being resolved away).
+fn resolve_incompatibilities(
+    incompat: &Incompatibility,
+    prior: &Incompatibility,
+    package: &str,
+) -> Incompatibility {
+    let mut terms: Vec<Term> = Vec::new();
+    for term in incompat.terms.iter().chain(prior.terms.iter()) {
+        if term.package == package {
+            continue;
+        }
+        if !terms.contains(term) {
+            terms.push(term.clone());
+        }
+    }
+    Incompatibility::new(terms)
+}
+
+fn next_undecided(solution: &PartialSolution) -> Option<String> {
+    solution.assignments.iter().find_map(|assignment| {
+        if assignment.term.positive
+            && !assignment.is_decision()
+            && solution.decided(&assignment.term.package).is_none()
+        {
+            Some(assignment.term.package.clone())
+        } else {
+            None
+        }
+    })
+}
+
+fn pick_version<'env>(
+    env: &'env impl PackageCollection,
+    name: &str,
+    selector: VersionSelector,
+    ranges: &[&VersionRange],
+    excluded: &[&Constraint],
+    locked: Option<&Version>,
+) -> Option<PackageInfo<'env>> {
+    let acceptable = |version: &Version| {
+        ranges.iter().all(|range| range.matches(version))
+            && !excluded.iter().any(|constraint| constraint.matches(version))
+    };
+
+    // Prefer the already-locked version, but only when it itself satisfies
+    // every accumulated constraint. Preferring a locked version that now falls
+    // outside the required range would let the solver decide an out-of-range
+    // version, backtrack off the resulting conflict, and then deterministically
+    // re-pick the very same version — a spurious conflict or an infinite loop.
+    if let Some(locked) = locked {
+        if acceptable(locked) {
+            if let Some(info) =
+                env.find_package_by_name(name, VersionSelector::specific_version(locked))
+            {
+                return Some(info);
+            }
+        }
+    }
+
+    // Fast path: the selector already honours the positive ranges and Unity
+    // compatibility. Take its pick unless a negative derivation excludes it.
+    let best = env.find_package_by_name(name, selector)?;
+    if !excluded.iter().any(|c| c.matches(best.version())) {
+        return Some(best);
+    }
+
+    // The selector's pick was forbidden by a learned clause; fall back to the
+    // highest enumerated version that satisfies every accumulated constraint.
+    env.find_packages(name)
+        .into_iter()
+        .filter(|info| acceptable(info.version()))
+        .max_by(|a, b| a.version().cmp(b.version()))
+}
+
+/// Build a diagnosable failure for `package`: the root→leaf chain reaching it
+/// and every requirer mapped to the range it demanded.
+fn build_no_solution(edges: &[Edge], package: &str) -> NoSolution {
+    let conflicting_requirements = edges
+        .iter()
+        .filter(|edge| edge.dependency == package)
+        .map(|edge| {
+            let requirer = edge.requirer.clone().unwrap_or_else(|| "<project>".to_owned());
+            (requirer, edge.range.clone())
+        })
+        .collect();
+
+    NoSolution {
+        package_path: chain_to(edges, package),
+        conflicting_requirements,
+    }
+}
+
+fn no_solution_from(incompat: &Incompatibility, edges: &[Edge]) -> NoSolution {
+    let package = incompat
+        .terms
+        .first()
+        .map(|term| term.package.clone())
+        .unwrap_or_default();
+    build_no_solution(edges, &package)
+}
+
+/// Walk the recorded edges backwards from `package` to a direct project
+/// dependency, producing the chain of package names from the root down.
+fn chain_to(edges: &[Edge], package: &str) -> Vec<String> {
+    let mut chain = vec![package.to_owned()];
+    let mut current = package.to_owned();
+    loop {
+        let Some(edge) = edges
+            .iter()
+            .find(|edge| edge.dependency == current && edge.requirer.is_some())
+        else {
+            break;
+        };
+        let requirer = edge.requirer.clone().expect("requirer present");
+        if chain.contains(&requirer) {
+            break; // guard against a dependency cycle
+        }
+        chain.insert(0, requirer.clone());
+        current = requirer;
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range_term() -> Term {
+        Term::positive("com.vrchat.base", Constraint::Range(VersionRange::all()))
+    }
+
+    #[test]
+    fn negate_flips_polarity_only() {
+        let term = range_term();
+        let negated = term.negate();
+        assert!(!negated.positive);
+        assert_eq!(term.package, negated.package);
+        assert_eq!(term.constraint, negated.constraint);
+        assert_eq!(negated.negate(), term);
+    }
+
+    #[test]
+    fn derived_term_is_not_rederived() {
+        // Regression test for the solver hanging: once a unit incompatibility's
+        // remaining term has been derived, evaluating it again must be a no-op
+        // rather than re-deriving it forever.
+        let mut solution = PartialSolution::default();
+        let incompat = Incompatibility::new(vec![range_term().negate()]);
+
+        match solution.relation(&incompat) {
+            Relation::AlmostSatisfied(index) => {
+                solution.derive(incompat.terms[index].negate(), 0);
+            }
+            _ => panic!("seed incompatibility should start almost satisfied"),
+        }
+
+        assert!(matches!(
+            solution.relation(&incompat),
+            Relation::Contradicted
+        ));
+        assert!(solution.already_derived(&range_term()));
+    }
+
+    #[test]
+    fn backtrack_drops_higher_level_decisions() {
+        let mut solution = PartialSolution::default();
+        solution.decide("a".to_owned(), Version::new(1, 0, 0));
+        solution.decide("b".to_owned(), Version::new(2, 0, 0));
+        assert_eq!(solution.decisions.len(), 2);
+
+        solution.backtrack(1);
+        assert_eq!(solution.level, 1);
+        assert!(solution.decided("a").is_some());
+        assert!(solution.decided("b").is_none());
+    }
+}